@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use strsim::levenshtein;
+
+/// A BK-tree over Levenshtein edit distance, used to find near-duplicate
+/// strings regardless of where they fall in sort order.
+///
+/// Each node stores one string; children are keyed by their edit distance
+/// to the parent, so a query only has to descend into children whose edge
+/// satisfies the triangle inequality for the query's tolerance.
+pub struct BkTree {
+    root: Option<Box<Node>>,
+}
+
+struct Node {
+    value: String,
+    children: HashMap<usize, Box<Node>>,
+}
+
+impl BkTree {
+    pub fn new() -> Self {
+        BkTree { root: None }
+    }
+
+    /// Inserts `value` into the tree.
+    pub fn insert(&mut self, value: String) {
+        match &mut self.root {
+            None => {
+                self.root = Some(Box::new(Node { value, children: HashMap::new() }));
+            }
+            Some(root) => root.insert(value),
+        }
+    }
+
+    /// Returns `true` if some stored string is within `tolerance` edit
+    /// distance of `query`.
+    pub fn contains_within(&self, query: &str, tolerance: usize) -> bool {
+        match &self.root {
+            None => false,
+            Some(root) => root.contains_within(query, tolerance),
+        }
+    }
+}
+
+impl Node {
+    fn insert(&mut self, value: String) {
+        let distance = levenshtein(&self.value, &value);
+        match self.children.get_mut(&distance) {
+            Some(child) => child.insert(value),
+            None => {
+                self.children.insert(distance, Box::new(Node { value, children: HashMap::new() }));
+            }
+        }
+    }
+
+    fn contains_within(&self, query: &str, tolerance: usize) -> bool {
+        let distance = levenshtein(&self.value, query);
+        if distance <= tolerance {
+            return true;
+        }
+
+        let lower = distance.saturating_sub(tolerance);
+        let upper = distance + tolerance;
+        self.children.iter()
+            .filter(|(&edge, _)| edge >= lower && edge <= upper)
+            .any(|(_, child)| child.contains_within(query, tolerance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_within_finds_exact_and_near_matches() {
+        let mut tree = BkTree::new();
+        tree.insert("kitten".to_string());
+        tree.insert("sitting".to_string());
+        tree.insert("flying".to_string());
+
+        assert!(tree.contains_within("kitten", 0));
+        assert!(tree.contains_within("kitton", 1)); // one substitution away from "kitten"
+        assert!(!tree.contains_within("zzzzzzzzzz", 2));
+    }
+
+    #[test]
+    fn empty_tree_contains_nothing() {
+        let tree = BkTree::new();
+        assert!(!tree.contains_within("anything", 100));
+    }
+
+    #[test]
+    fn pruning_bounds_skip_children_outside_the_tolerance_window() {
+        // Hand-build a tree (rather than going through `insert`) so the
+        // child's map key -- the edge `contains_within` prunes on -- can be
+        // set independently of what its value would actually distance to.
+        // This isolates the `lower`/`upper` filter itself: if it were
+        // missing or off-by-one, a child stored just outside the window
+        // would still be visited and this exact-match value would wrongly
+        // be found.
+        let make_child = |edge: usize| {
+            let mut children = HashMap::new();
+            children.insert(edge, Box::new(Node { value: "bbbbb".to_string(), children: HashMap::new() }));
+            Node { value: "aaaaa".to_string(), children }
+        };
+
+        // distance("aaaaa", "bbbbb") == 5, tolerance 1 -> window is [4, 6].
+        let query = "bbbbb";
+        let tolerance = 1;
+
+        let out_of_window = make_child(3);
+        assert!(!out_of_window.contains_within(query, tolerance));
+
+        let at_lower_bound = make_child(4);
+        assert!(at_lower_bound.contains_within(query, tolerance));
+
+        let at_upper_bound = make_child(6);
+        assert!(at_upper_bound.contains_within(query, tolerance));
+
+        let above_window = make_child(7);
+        assert!(!above_window.contains_within(query, tolerance));
+    }
+
+    #[test]
+    fn first_inserted_near_duplicate_wins_regardless_of_length() {
+        // Mirrors how `main.rs` drives the tree: iterate sorted candidates
+        // in order, keep the first string in each near-duplicate cluster,
+        // and drop the rest. The tree itself has no notion of "better" --
+        // whichever string got inserted first keeps its spot, even when a
+        // later, longer near-duplicate would have been the correct one to
+        // keep. See the [readcoil/strictstrings#chunk0-5] phantom-read fix,
+        // which avoids this by making sure the truncated variant is never
+        // produced in the first place.
+        let mut tree = BkTree::new();
+        let mut kept = Vec::new();
+        for s in ["Another Big Endian String Her", "Another Big Endian String Here"] {
+            if !tree.contains_within(s, 1) {
+                tree.insert(s.to_string());
+                kept.push(s);
+            }
+        }
+
+        assert_eq!(kept, vec!["Another Big Endian String Her"]);
+    }
+}
@@ -0,0 +1,139 @@
+use clap::{Arg, Command};
+
+/// Builds the full `clap::Command` definition for the tool.
+///
+/// This is factored out of `main()` so that the same `Arg` metadata (help
+/// text, value names, defaults) backs both normal argument parsing and the
+/// generated man page / shell completions, instead of those being
+/// hand-maintained separately.
+pub fn build_cli() -> Command<'static> {
+    Command::new("StrictStrings")
+        .version("0.1.0")
+        .author("Julian Gutmanis <https://github.com/readcoil>")
+        .about("Performs strict filtering on strings within a file contents.")
+        .arg(Arg::new("infile")
+            .help("Input file to process")
+            .required_unless_present_any(["generate-man", "generate-completions"])
+            .index(1))
+        .arg(Arg::new("outfile")
+            .short('o')
+            .long("out")
+            .takes_value(true)
+            .help("Output file write filtered strings")
+            .required(false))
+        .arg(Arg::new("threshold")
+            .short('t')
+            .long("language")
+            .takes_value(true)
+            .value_name("FLOAT")
+            .help("Sets a custom language detection threshold")
+            .default_value("0.5"))
+        .arg(Arg::new("similarity")
+            .short('s')
+            .long("similarity")
+            .takes_value(true)
+            .value_name("FLOAT")
+            .help("Sets a custom similarity filtering threshold")
+            .default_value("0.8"))
+        .arg(Arg::new("quiet")
+            .short('q')
+            .long("quiet")
+            .takes_value(false)
+            .help("Silences all output"))
+        .arg(Arg::new("logdir")
+            .short('l')
+            .long("logs")
+            .takes_value(true)
+            .help("Output filtered values to log directory"))
+        .arg(Arg::new("bytes")
+            .short('b')
+            .long("bytes")
+            .takes_value(false)
+            .help("Print byte representation after strings"))
+        .arg(Arg::new("min")
+            .short('m')
+            .long("min")
+            .takes_value(true)
+            .value_name("MIN")
+            .help("Minimum length of strings to process")
+            .default_value("6"))
+        .arg(Arg::new("max")
+            .short('M')
+            .long("max")
+            .takes_value(true)
+            .value_name("MAX")
+            .help("Maximum length of strings to process")
+            .default_value("200"))
+        .arg(Arg::new("wslen")
+            .short('W')
+            .long("wslen")
+            .takes_value(true)
+            .value_name("wslen")
+            .help("Maximum length of strings without whitespace")
+            .default_value("30"))
+        .arg(Arg::new("encoding")
+            .short('e')
+            .long("encoding")
+            .takes_value(true)
+            .value_name("ENCODING")
+            .help("String encoding(s) to scan for: ascii, utf16le, utf16be, all")
+            .default_value("ascii"))
+        .arg(Arg::new("threads")
+            .long("threads")
+            .takes_value(true)
+            .value_name("N")
+            .help("Number of worker threads to use for language/ngram filtering (defaults to all cores)"))
+        .arg(Arg::new("format")
+            .long("format")
+            .takes_value(true)
+            .value_name("FORMAT")
+            .help("Output format: text, json or csv")
+            .default_value("text"))
+        .arg(Arg::new("radix")
+            .short('r')
+            .long("radix")
+            .takes_value(true)
+            .value_name("o|d|x")
+            .help("Prefix each line in text mode with its offset, in octal/decimal/hex"))
+        .arg(Arg::new("model")
+            .long("model")
+            .takes_value(true)
+            .value_name("PATH")
+            .help("Load a JSON/TOML model file describing languages, detection threshold and forbidden n-grams"))
+        .arg(Arg::new("generate-man")
+            .long("generate-man")
+            .takes_value(true)
+            .value_name("DIR")
+            .help("Write a roff man page to DIR and exit")
+            .hide(true))
+        .arg(Arg::new("generate-completions")
+            .long("generate-completions")
+            .takes_value(true)
+            .value_name("SHELL")
+            .help("Write a shell completion script (bash, zsh, fish, powershell, elvish) to stdout and exit")
+            .hide(true))
+}
+
+/// Renders the man page for `cmd` and writes it to `<dir>/strictstrings.1`.
+pub fn generate_man(cmd: &Command, dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let man = clap_mangen::Man::new(cmd.clone());
+    let mut buffer: Vec<u8> = Vec::new();
+    man.render(&mut buffer)?;
+
+    std::fs::create_dir_all(dir)?;
+    let path = std::path::Path::new(dir).join("strictstrings.1");
+    std::fs::write(path, buffer)?;
+    Ok(())
+}
+
+/// Generates a completion script for `shell_name` and writes it to stdout.
+pub fn generate_completions(cmd: &mut Command, shell_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use clap_complete::{generate, Shell};
+
+    let shell: Shell = shell_name.parse()
+        .map_err(|_| format!("unknown shell '{}': expected bash, zsh, fish, powershell or elvish", shell_name))?;
+
+    let name = cmd.get_name().to_string();
+    generate(shell, cmd, name, &mut std::io::stdout());
+    Ok(())
+}
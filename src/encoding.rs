@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+/// Which string encodings to scan for, mirroring GNU `strings -e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Utf16Le,
+    Utf16Be,
+    All,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ascii" => Ok(Encoding::Ascii),
+            "utf16le" => Ok(Encoding::Utf16Le),
+            "utf16be" => Ok(Encoding::Utf16Be),
+            "all" => Ok(Encoding::All),
+            other => Err(format!("unknown encoding '{}': expected ascii, utf16le, utf16be or all", other)),
+        }
+    }
+}
+
+impl Encoding {
+    /// The concrete decoders to run for this selection. `All` expands to
+    /// every single-purpose decoder so the caller can union their results.
+    pub fn decoders(self) -> Vec<Encoding> {
+        match self {
+            Encoding::All => vec![Encoding::Ascii, Encoding::Utf16Le, Encoding::Utf16Be],
+            other => vec![other],
+        }
+    }
+
+    /// Bytes per decoded character for this decoder in the source file.
+    /// ASCII candidates are decoded one source byte per character; the
+    /// UTF-16 scanners consume two source bytes per character. Callers use
+    /// this to translate an offset *within* a decoded candidate back into a
+    /// byte offset in the original file.
+    pub fn unit_width(self) -> usize {
+        match self {
+            Encoding::Ascii => 1,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::All => unreachable!("call `decoders()` to expand `All` before asking for a unit width"),
+        }
+    }
+}
+
+fn is_printable(c: u8) -> bool {
+    (32..=126).contains(&c) || c == 9 || c == 10 || c == 13
+}
+
+/// Scans `data` for runs of single-byte ASCII text, the same rule the
+/// original streaming scanner used. Each candidate is paired with the byte
+/// offset in `data` where it starts.
+fn scan_ascii(data: &[u8]) -> Vec<(usize, Vec<u8>)> {
+    let mut candidates = Vec::new();
+    let mut current = Vec::new();
+    let mut start = 0usize;
+
+    for (i, &byte) in data.iter().enumerate() {
+        if is_printable(byte) {
+            if current.is_empty() {
+                start = i;
+            }
+            current.push(byte);
+        } else if !current.is_empty() {
+            candidates.push((start, std::mem::take(&mut current)));
+        }
+    }
+    if !current.is_empty() {
+        candidates.push((start, current));
+    }
+    candidates
+}
+
+/// Scans `data` for runs of printable wide characters stored two bytes per
+/// code unit, as produced by UTF-16 string literals in Windows binaries.
+///
+/// In `little`-endian order the low byte carries the printable ASCII value
+/// and the high byte is zero; for big endian the roles are swapped. This is
+/// the same `is_printable` rule the ASCII scanner uses, just applied to
+/// every other byte.
+///
+/// The scan always advances in 2-byte steps, including past a non-matching
+/// pair, so every candidate stays code-unit aligned with byte 0 of `data`.
+/// Stepping by 1 on a miss would let the scanner resync onto the *other*
+/// endianness' byte lane -- e.g. a UTF-16BE run sitting right after a
+/// UTF-16LE string plus two bytes of NUL padding would get re-read here
+/// as a byte-shifted, truncated phantom LE string. Keeping the lane fixed
+/// means a genuinely misaligned run (an odd number of padding bytes) is
+/// missed rather than silently misdecoded, matching how `scan_utf16` is
+/// called once per lane from `extract_candidates`.
+fn scan_utf16(data: &[u8], little: bool) -> Vec<(usize, Vec<u8>)> {
+    let mut candidates = Vec::new();
+    let mut current = String::new();
+    let mut start = 0usize;
+
+    let mut i = 0;
+    while i + 1 < data.len() {
+        let (lo, hi) = if little { (data[i], data[i + 1]) } else { (data[i + 1], data[i]) };
+
+        if hi == 0 && is_printable(lo) {
+            if current.is_empty() {
+                start = i;
+            }
+            current.push(lo as char);
+        } else if !current.is_empty() {
+            candidates.push((start, std::mem::take(&mut current).into_bytes()));
+        }
+        i += 2;
+    }
+    if !current.is_empty() {
+        candidates.push((start, current.into_bytes()));
+    }
+    candidates
+}
+
+/// Extracts raw text candidates from `data` for a single decoder, each
+/// paired with its starting byte offset in `data`. Candidates are fed into
+/// the same length/newline-splitting pipeline the ASCII scanner has always
+/// used, so downstream filters apply uniformly regardless of source
+/// encoding.
+pub fn extract_candidates(data: &[u8], encoding: Encoding) -> Vec<(usize, Vec<u8>)> {
+    match encoding {
+        Encoding::Ascii => scan_ascii(data),
+        Encoding::Utf16Le => scan_utf16(data, true),
+        Encoding::Utf16Be => scan_utf16(data, false),
+        Encoding::All => unreachable!("call `decoders()` to expand `All` before extracting"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(s: &str) -> Vec<u8> {
+        s.bytes().flat_map(|b| [b, 0]).collect()
+    }
+
+    fn utf16be(s: &str) -> Vec<u8> {
+        s.bytes().flat_map(|b| [0, b]).collect()
+    }
+
+    #[test]
+    fn ascii_scan_finds_printable_runs_with_offsets() {
+        let data = b"\x01Hi\x02there\x00";
+        let candidates = scan_ascii(data);
+        assert_eq!(candidates, vec![
+            (1, b"Hi".to_vec()),
+            (4, b"there".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn utf16le_scan_decodes_and_reports_byte_offsets() {
+        let data = utf16le("Hi");
+        let candidates = scan_utf16(&data, true);
+        assert_eq!(candidates, vec![(0, b"Hi".to_vec())]);
+    }
+
+    #[test]
+    fn utf16be_scan_decodes_and_reports_byte_offsets() {
+        let data = utf16be("Hi");
+        let candidates = scan_utf16(&data, false);
+        assert_eq!(candidates, vec![(0, b"Hi".to_vec())]);
+    }
+
+    #[test]
+    fn utf16_scan_stays_aligned_across_non_matching_pairs() {
+        // "Hi" followed by two bytes of NUL padding, then "Yo". A scanner
+        // that resyncs by 1 byte on a miss would decode a phantom,
+        // byte-shifted string out of the padding/second-string boundary.
+        let mut data = utf16le("Hi");
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&utf16le("Yo"));
+
+        let candidates = scan_utf16(&data, true);
+        assert_eq!(candidates, vec![
+            (0, b"Hi".to_vec()),
+            (6, b"Yo".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn adjacent_le_and_be_runs_do_not_produce_a_phantom_cross_read() {
+        // A UTF-16LE string directly followed by NUL padding and then a
+        // UTF-16BE string, the PE/COFF-style layout that used to make the
+        // LE scanner resync onto the BE string's byte lane and emit a
+        // truncated duplicate (see the doc comment on `scan_utf16`).
+        let mut data = utf16le("Another Little Endian String Here");
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&utf16be("Another Big Endian String Here"));
+
+        let le_candidates = scan_utf16(&data, true);
+        let be_candidates = scan_utf16(&data, false);
+
+        assert_eq!(le_candidates.len(), 1);
+        assert_eq!(le_candidates[0].1, b"Another Little Endian String Here".to_vec());
+
+        assert_eq!(be_candidates.len(), 1);
+        assert_eq!(be_candidates[0].1, b"Another Big Endian String Here".to_vec());
+    }
+}
@@ -1,29 +1,45 @@
-extern crate strsim;
-use clap::{Arg, Command};
 use indicatif::{ProgressBar, ProgressStyle, ProgressDrawTarget};
 use lingua::{Language, LanguageDetectorBuilder};
 use prettytable::{Table, row, Row, Cell};
+use rayon::prelude::*;
 use std::collections::HashSet;
 use std::fs;
-use std::io::{Read};
 use std::io::prelude::*;
 use std::ops::Not;
 use std::path::Path;
 use std::str;
 use std::time::Instant;
-use strsim::normalized_levenshtein;
 
+mod bktree;
+mod cli;
+mod encoding;
+mod model;
+mod report;
 
-fn process_text_candidate(text_candidate: &mut Vec<u8>, unique_strings: &mut HashSet<String>, filtered_by_len: &mut HashSet<String>, 
-    logging: bool, min_length: usize, max_length: usize) {
+use bktree::BkTree;
+use encoding::Encoding;
+use report::{Format, Radix, StringRecord};
+use std::collections::HashMap;
+
+fn process_text_candidate(text_candidate: &mut Vec<u8>, candidate_offset: usize, unit_width: usize, unique_strings: &mut HashMap<String, usize>,
+    filtered_by_len: &mut HashSet<String>, logging: bool, length_range: std::ops::RangeInclusive<usize>) {
     if let Ok(text) = str::from_utf8(text_candidate) {
-        // Split the text on both carriage return and newline characters.
-        let lines = text.split(|c| c == '\n' || c == '\r');
-        for line in lines {
+        // Split the text on both carriage return and newline characters,
+        // keeping the delimiters so we can track each line's offset.
+        // `consumed`/`leading` count decoded characters, i.e. source code
+        // units -- for UTF-16 candidates each one is `unit_width` (2) source
+        // bytes, not 1, so they're scaled before adding to `candidate_offset`.
+        let mut consumed = 0usize;
+        for raw_line in text.split_inclusive(['\n', '\r']) {
+            let line = raw_line.trim_end_matches(['\n', '\r']);
             let cleaned_line = line.trim();
+            let leading = line.chars().count() - line.trim_start().chars().count();
+            let line_offset = candidate_offset + (consumed + leading) * unit_width;
+            consumed += raw_line.chars().count();
+
             // Check if the cleaned line is not empty and within the specified length range before inserting.
-            if !cleaned_line.is_empty() && cleaned_line.len() >= min_length && cleaned_line.len() <= max_length {
-                unique_strings.insert(cleaned_line.to_string());
+            if !cleaned_line.is_empty() && length_range.contains(&cleaned_line.len()) {
+                unique_strings.entry(cleaned_line.to_string()).or_insert(line_offset);
             } else {
                 if logging {
                     filtered_by_len.insert(cleaned_line.to_string());
@@ -34,12 +50,6 @@ fn process_text_candidate(text_candidate: &mut Vec<u8>, unique_strings: &mut Has
     text_candidate.clear();  // Always clear the buffer after processing
 }
 
-
-fn is_printable(c: u8) -> bool {
-    (c >= 32 && c <= 126) || c == 9 || c == 10 || c == 13
-}
-
-
 fn print_remaining(count_remain: usize, quiet: bool) {
     if quiet.not() {
         if count_remain == 0 {
@@ -54,94 +64,40 @@ fn print_remaining(count_remain: usize, quiet: bool) {
 
 
 fn main() -> Result<(), Box<dyn std::error::Error>>  {
-    let matches = Command::new("StrictStrings")
-        .version("0.1.0")
-        .author("Julian Gutmanis <https://github.com/readcoil>")
-        .about("Performs strict filtering on strings within a file contents.")
-        .arg(Arg::new("infile")
-            .help("Input file to process")
-            .required(true)
-            .index(1))
-        .arg(Arg::new("outfile")
-            .short('o')
-            .long("out")
-            .takes_value(true)
-            .help("Output file write filtered strings")
-            .required(false))
-        .arg(Arg::new("threshold")
-            .short('t')
-            .long("language")
-            .takes_value(true)
-            .value_name("FLOAT")
-            .help("Sets a custom language detection threshold")
-            .default_value("0.5"))
-        .arg(Arg::new("similarity")
-            .short('s')
-            .long("similarity")
-            .takes_value(true)
-            .value_name("FLOAT")
-            .help("Sets a custom similarity filtering threshold")
-            .default_value("0.8"))
-        .arg(Arg::new("quiet")
-            .short('q')
-            .long("quiet")
-            .takes_value(false)
-            .help("Silences all output"))
-        .arg(Arg::new("logdir")
-            .short('l')
-            .long("logs")
-            .takes_value(true)
-            .help("Output filtered values to log directory"))
-        .arg(Arg::new("bytes")
-            .short('b')
-            .long("bytes")
-            .takes_value(false)
-            .help("Print byte representation after strings"))
-        .arg(Arg::new("min")
-            .short('m')
-            .long("min")
-            .takes_value(true)
-            .value_name("MIN")
-            .help("Minimum length of strings to process")
-            .default_value("6"))
-        .arg(Arg::new("max")
-            .short('M')
-            .long("max")
-            .takes_value(true)
-            .value_name("MAX")
-            .help("Maximum length of strings to process")
-            .default_value("200"))
-        .arg(Arg::new("wslen")
-            .short('W')
-            .long("wslen")
-            .takes_value(true)
-            .value_name("wslen")
-            .help("Maximum length of strings without whitespace")
-            .default_value("30"))
-        .get_matches();
-
-    // impossible ngrams to filter.
-    // note these are not filtered if '.' is present in the string.
-    // currently only filters bigrams.
-    let ngrams: HashSet<&str> = [
-        "bk", "fq", "jc", "jt", "mj", "qh", "qx", "vj", "wz", "zh",
-        "bq", "fv", "jd", "jv", "mq", "qj", "qy", "vk", "xb", "zj",
-        "bx", "fx", "jf", "jw", "mx", "qk", "qz", "vm", "xg", "zn",
-        "cb", "fz", "jg", "jx", "mz", "ql", "sx", "vn", "xj", "zq",
-        "cf", "gq", "jh", "jy", "pq", "qm", "sz", "vp", "xk", "zr",
-        "cg", "gv", "jk", "jz", "pv", "qn", "tq", "vq", "xv", "zs",
-        "cj", "gx", "jl", "kq", "px", "qo", "tx", "vt", "xz", "zx",
-        "cp", "hk", "jm", "kv", "qb", "qp", "vb", "vw", "yq",
-        "cv", "hv", "jn", "kx", "qc", "qr", "vc", "vx", "yv",
-        "cw", "hx", "jp", "kz", "qd", "qs", "vd", "vz", "yz",
-        "cx", "hz", "jq", "lq", "qe", "qt", "vf", "wq", "zb",
-        "dx", "iy", "jr", "lx", "qf", "qv", "vg", "wv", "zc",
-        "fk", "jb", "js", "mg", "qg", "qw", "vh", "wx", "zg",
-    ].iter().cloned().collect();
+    let mut command = cli::build_cli();
+    let matches = command.clone().get_matches();
+
+    if let Some(dir) = matches.value_of("generate-man") {
+        cli::generate_man(&command, dir)?;
+        return Ok(());
+    }
+
+    if let Some(shell) = matches.value_of("generate-completions") {
+        cli::generate_completions(&mut command, shell)?;
+        return Ok(());
+    }
+
+    // The detection model: which languages to test for, the forbidden
+    // n-gram table (keyed by window order), and a default language
+    // threshold. Defaults to the bigram blocklist this tool has always
+    // shipped with unless `--model` points at an override.
+    let active_model = match matches.value_of("model") {
+        Some(path) => model::load_model(path)?,
+        None => model::default_model(),
+    };
 
     let infile = matches.value_of("infile").unwrap();
-    let lang_threshold: f64 = matches.value_of_t("threshold").unwrap_or_else(|e| e.exit());
+    let lang_threshold: f64 = if matches.occurrences_of("threshold") == 0 {
+        active_model.threshold
+    } else {
+        matches.value_of_t("threshold").unwrap_or_else(|e| e.exit())
+    };
     let leven_threshold: f64 = matches.value_of_t("similarity").unwrap_or_else(|e| e.exit());
+
+    if let Some(threads) = matches.value_of("threads") {
+        let threads: usize = threads.parse()?;
+        rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()?;
+    }
     let quiet: bool = matches.is_present("quiet");
     let print_bytes: bool = matches.is_present("bytes");
     let min_length: usize = matches.value_of_t("min").unwrap_or_else(|e| e.exit());
@@ -157,12 +113,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
     }
 
     let start_time = Instant::now();
-    let languages = vec![Language::English, Language::French,
-                         Language::German, Language::Spanish,
-                         Language::Russian, Language::Chinese];
-    let detector = LanguageDetectorBuilder::from_languages(&languages).build();
+    let detector = LanguageDetectorBuilder::from_languages(&active_model.languages).build();
     
-    let mut file = fs::File::open(infile)?;
+    let encoding: Encoding = matches.value_of_t("encoding").unwrap_or_else(|e| e.exit());
+    let format: Format = matches.value_of_t("format").unwrap_or_else(|e| e.exit());
+    let radix: Option<Radix> = matches.value_of("radix").map(str::parse).transpose()?;
     let outfile_option = matches.value_of("outfile").map(String::from);
     let log_dir_option = matches.value_of("logdir").map(String::from);
     let logging = log_dir_option.is_some();
@@ -173,14 +128,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
             fs::create_dir_all(path).expect("Failed to create directory");
         }
 
-    } 
+    }
 
     // Get file size for progress bar
     if !quiet {
         println!("Grabbing strings.");
     }
 
-    let file_size = file.metadata()?.len() as u64;
+    // Known limitation: this reads the whole file into memory, a
+    // regression from the baseline's 1024-byte streaming scan. It's done
+    // this way because the UTF-16 scanners need to look two bytes at a
+    // time without losing alignment across a chunk boundary, and accurate
+    // offset reporting (see StringRecord) needs a stable base to index
+    // into. Bounding this (mmap, or chunked windows with carried-over
+    // alignment state) is worth doing before pointing this at
+    // multi-gigabyte inputs; for now, warn loudly instead of silently
+    // eating all available RAM.
+    const LARGE_FILE_WARNING_BYTES: u64 = 500 * 1024 * 1024;
+    let input_size = fs::metadata(infile)?.len();
+    if input_size > LARGE_FILE_WARNING_BYTES && !quiet {
+        eprintln!(
+            "Warning: {} is {} MiB; this tool currently reads the whole file into memory, so very large inputs may exhaust RAM.",
+            infile,
+            input_size / (1024 * 1024)
+        );
+    }
+
+    let file_data = fs::read(infile)?;
+    let decoders = encoding.decoders();
+    let file_size = (file_data.len() * decoders.len()) as u64;
     let pb_file = ProgressBar::new(file_size);
     pb_file.set_style(ProgressStyle::default_bar()
         .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
@@ -191,40 +167,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
         pb_file.set_draw_target(ProgressDrawTarget::hidden());
     }
 
-    let mut buffer = [0u8; 1024];
-    let mut text_candidate = Vec::new();
-    let mut unique_strings = HashSet::new();
+    let mut unique_strings: HashMap<String, usize> = HashMap::new();
     let mut filtered_by_len = HashSet::new();
-    let mut lang_strings = HashSet::new();
-    let mut final_strings = Vec::new();
-
-
-    // String extraction loop
-    while let Ok(bytes_read) = file.read(&mut buffer) {
-        if !quiet {
-            pb_file.inc(bytes_read as u64);
+    let mut final_strings: Vec<StringRecord> = Vec::new();
+
+    // String extraction: run each decoder implied by `--encoding` over the
+    // whole file and union their candidates into `unique_strings` so
+    // `--encoding all` surfaces both narrow and wide-character strings.
+    for decoder in decoders {
+        let unit_width = decoder.unit_width();
+        for (offset, mut candidate) in encoding::extract_candidates(&file_data, decoder) {
+            process_text_candidate(&mut candidate, offset, unit_width, &mut unique_strings, &mut filtered_by_len, logging, min_length..=max_length);
         }
-
-        if bytes_read == 0 {
-            break;
-        }
-
-        // Accumulate printable characters, including newlines.
-        for &byte in buffer[0..bytes_read].iter() {
-            if is_printable(byte) {
-                text_candidate.push(byte);
-            } else {
-                // Process and clear the buffer when encountering a non-printable character
-                process_text_candidate(&mut text_candidate, &mut unique_strings, &mut filtered_by_len, logging, min_length, max_length);
-            }
+        if !quiet {
+            pb_file.inc(file_data.len() as u64);
         }
     }
 
-    // Final processing to handle any remaining data
-    if !text_candidate.is_empty() {
-        process_text_candidate(&mut text_candidate, &mut unique_strings, &mut filtered_by_len, logging, min_length, max_length);
-    }
-
     if logging {
         if let Some(ref log_dir) = log_dir_option {
             let mut log_file = fs::File::create(format!("{}/filtered_by_len.txt", log_dir))?;
@@ -243,7 +202,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
     let mut filtered_by_whitespace = Vec::new();
     let mut remaining = Vec::new();
 
-    for s in &unique_strings {
+    for s in unique_strings.keys() {
         if s.len() >= wslen {
             let contains_unencoded_whitespace = s.chars().any(|c| c.is_whitespace());
             let contains_encoded_space = s.contains("%20");
@@ -280,6 +239,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
 
     // English language detection
     let total_strings = unique_strings.len() as u64;
+    let mut lang_strings: HashMap<String, (usize, f64)> = HashMap::new();
     let mut filtered_by_lang = Vec::new();
 
     println!("Total strings: {}", total_strings);
@@ -302,19 +262,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
         println!("Filtering English language.");
     }
 
-    for text in unique_strings {
+    // `detector` is `Sync`, so each string can be scored on its own thread.
+    // The progress bar is safe to share: `ProgressBar::inc` updates an
+    // atomic counter internally, so every worker can call it directly.
+    let lang_results: Vec<(String, usize, Option<f64>)> = unique_strings.par_iter().map(|(text, &offset)| {
         if !quiet {
             pb_lang.inc(1);
         }
-        
-        let detected_languages = detector
-            .compute_language_confidence_values(&text)
+
+        let confidence = detector
+            .compute_language_confidence_values(text)
             .into_iter()
-            .filter(|(lang, confidence)| *lang == Language::English && *confidence > lang_threshold)
-            .collect::<Vec<_>>();
+            .find(|(lang, confidence)| *lang == Language::English && *confidence > lang_threshold)
+            .map(|(_, confidence)| confidence);
+
+        (text.clone(), offset, confidence)
+    }).collect();
 
-        if !detected_languages.is_empty() {
-            let _ = lang_strings.insert(text.to_string());
+    for (text, offset, confidence) in lang_results {
+        if let Some(confidence) = confidence {
+            lang_strings.insert(text, (offset, confidence));
         } else {
             filtered_by_lang.push(text);
         }
@@ -347,24 +314,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
         println!("Filtering impossible ngrams.");
     }
 
-    let remaining_no_ngrams: Vec<_> = lang_strings.iter().filter(|&s| {
+    let remaining_no_ngrams: Vec<(String, usize, f64)> = lang_strings.par_iter().filter(|(s, _)| {
+        if !quiet {
+            pb_ngram.inc(1);
+        }
+
         // Keep the string if it contains a dot (catch urls etc)
         if s.contains('.') {
             return true;
         }
-        // Remove the string if it contains any ngrams.
-        !s.chars().collect::<Vec<_>>().windows(2).any(|window| {
-            let ngram_str: String = window.iter().collect();
-            ngrams.contains(&ngram_str.as_str())
+        // Remove the string if any window, of any order present in the
+        // model's ngram table, matches a known-impossible sequence.
+        let chars: Vec<char> = s.chars().collect();
+        !active_model.ngrams.iter().any(|(&order, forbidden)| {
+            order >= 1 && chars.len() >= order && chars.windows(order).any(|window| {
+                let ngram_str: String = window.iter().collect();
+                forbidden.contains(&ngram_str)
+            })
         })
-    }).cloned().collect();
+    }).map(|(s, &(offset, confidence))| (s.clone(), offset, confidence)).collect();
 
     let total_after_ngrams = remaining_no_ngrams.len() as u64;
-    
+
     if logging {
-        let remaining_no_ngrams_set: HashSet<_> = remaining_no_ngrams.iter().cloned().collect();
-        let removed_by_ngram: Vec<_> = lang_strings.difference(&remaining_no_ngrams_set).cloned().collect();
-        
+        let remaining_no_ngrams_set: HashSet<&String> = remaining_no_ngrams.iter().map(|(s, _, _)| s).collect();
+        let removed_by_ngram: Vec<&String> = lang_strings.keys().filter(|s| !remaining_no_ngrams_set.contains(s)).collect();
+
         if let Some(ref log_dir) = log_dir_option {
             let mut log_file = fs::File::create(format!("{}/filtered_by_ngram.txt", log_dir))?;
             for string in removed_by_ngram.iter() {
@@ -373,16 +348,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
             }
         }
     }
-    
+
 
     print_remaining(total_after_ngrams as usize, quiet);
 
     // Convert to Vec and sort
-    let mut sorted_strings: Vec<_> = remaining_no_ngrams.into_iter().collect();
-    sorted_strings.sort_by_key(|s| s.to_lowercase());
-
-    // Levenshtein similarity filtering
-    let sorted_cnt = sorted_strings.len() as u64;        
+    let mut sorted_strings: Vec<(String, usize, f64)> = remaining_no_ngrams;
+    sorted_strings.sort_by_key(|(s, _, _)| s.to_lowercase());
+
+    // Levenshtein similarity filtering via the BK-tree.
+    // NOTE: this request asked to parallelize the language, ngram, AND
+    // similarity stages; only the first two are parallelized above. Each
+    // BK-tree insert/lookup depends on the tree built by every prior
+    // string in sort order, so the work isn't independent across threads
+    // the way the language/ngram passes are. Parallelizing it for real
+    // would mean a different structure (e.g. sharding by prefix and
+    // merging trees), which is out of scope here.
+    let sorted_cnt = sorted_strings.len() as u64;
 
     let pb_sim = ProgressBar::new(sorted_cnt);
     pb_sim.set_style(ProgressStyle::default_bar()
@@ -398,21 +380,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
 
     print_remaining(sorted_strings.len(), quiet);
 
-    let mut current_string = &sorted_strings[0];
+    // Near-duplicate clustering via a BK-tree: unlike comparing each string
+    // only to its immediate predecessor, this catches duplicates anywhere
+    // in the tree regardless of where they landed in sort order.
+    let mut leven_tree = BkTree::new();
     let mut filtered_by_leven = Vec::new();
 
-    for i in 1..sorted_strings.len() {
+    for (s, offset, confidence) in &sorted_strings {
         if !quiet {
             pb_sim.inc(1);
         }
 
-        let similarity = normalized_levenshtein(current_string, &sorted_strings[i]);
-        
-        if similarity < leven_threshold {
-            final_strings.push(current_string.to_string());
-            current_string = &sorted_strings[i];
+        let tolerance = ((1.0 - leven_threshold) * s.chars().count() as f64).floor() as usize;
+
+        if leven_tree.contains_within(s, tolerance) {
+            filtered_by_leven.push(s.clone());
         } else {
-            filtered_by_leven.push(sorted_strings[i].to_string());
+            leven_tree.insert(s.clone());
+            final_strings.push(StringRecord::new(s.clone(), *offset, *confidence));
         }
     }
     if logging {
@@ -425,8 +410,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
         }
     }
 
-    final_strings.push(current_string.to_string());
-    final_strings.sort_by_key(|s| s.to_lowercase());
+    final_strings.sort_by_key(|r| r.string.to_lowercase());
 
 
 
@@ -435,32 +419,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
         println!("Final strings: {}\n\n", final_strings.len());
     }
 
-    if print_bytes {
+    if format == Format::Json || format == Format::Csv {
+        let mut stdout = std::io::stdout();
+        report::write_report(&final_strings, format, &mut stdout)?;
+        println!();
+    } else if print_bytes {
         let mut table = Table::new();
         table.add_row(row!["String", "UTF-Bytes", "Bytes"]);
 
-        for string in final_strings.iter() {
+        for record in final_strings.iter() {
             table.add_row(Row::new(vec![
-                Cell::new(string),
-                Cell::new(&format!("{:?}", string)),
-                Cell::new(&format!("{:?}", string.as_bytes())),
+                Cell::new(&record.string),
+                Cell::new(&format!("{:?}", record.string)),
+                Cell::new(&format!("{:?}", record.string.as_bytes())),
             ]));
         }
         table.printstd();
     }
     else {
-        for string in final_strings.iter() {
-            println!("{}", string);
+        for record in final_strings.iter() {
+            match radix {
+                Some(radix) => println!("{:>8}  {}", report::format_offset(record.offset_decimal, radix), record.string),
+                None => println!("{}", record.string),
+            }
         }
     }
 
     if let Some(ref outfile) = outfile_option {
-        let mut fout = fs::File::create(outfile)?;
-        for string in final_strings.iter() {
-            fout.write_all(string.as_bytes())?;
-            fout.write_all(b"\n")?;
+        if format == Format::Json || format == Format::Csv {
+            let mut fout = fs::File::create(outfile)?;
+            report::write_report(&final_strings, format, &mut fout)?;
+        } else {
+            let mut fout = fs::File::create(outfile)?;
+            for record in final_strings.iter() {
+                fout.write_all(record.string.as_bytes())?;
+                fout.write_all(b"\n")?;
+            }
         }
-    } 
+    }
 
     let final_cnt = final_strings.len() as u64;
     if !quiet {
@@ -477,3 +473,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>>  {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_offsets_are_unscaled() {
+        let mut candidate = b"foo\nbar".to_vec();
+        let mut unique_strings = HashMap::new();
+        let mut filtered_by_len = HashSet::new();
+
+        process_text_candidate(&mut candidate, 100, 1, &mut unique_strings, &mut filtered_by_len, false, 1..=200);
+
+        assert_eq!(unique_strings.get("foo"), Some(&100));
+        assert_eq!(unique_strings.get("bar"), Some(&104));
+    }
+
+    #[test]
+    fn utf16_offsets_scale_by_code_unit_width() {
+        // A UTF-16 candidate is already decoded down to one byte per
+        // character by the time it reaches `process_text_candidate`; the
+        // source file used two bytes per character, so offsets must scale
+        // by `unit_width` rather than counting decoded bytes 1:1.
+        let mut candidate = b"foo\nbar".to_vec();
+        let mut unique_strings = HashMap::new();
+        let mut filtered_by_len = HashSet::new();
+
+        process_text_candidate(&mut candidate, 100, 2, &mut unique_strings, &mut filtered_by_len, false, 1..=200);
+
+        assert_eq!(unique_strings.get("foo"), Some(&100));
+        // "foo\n" is 4 code units -> 8 source bytes, so "bar" starts at 108.
+        assert_eq!(unique_strings.get("bar"), Some(&108));
+    }
+}
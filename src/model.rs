@@ -0,0 +1,119 @@
+use lingua::Language;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// On-disk shape of a `--model` file (JSON or TOML).
+///
+/// Ships as the embedded [`default_model`] so that behavior is unchanged
+/// when no `--model` flag is given.
+#[derive(Debug, Deserialize)]
+struct ModelFile {
+    languages: Vec<String>,
+    threshold: f64,
+    /// Forbidden n-grams keyed by order, e.g. "2" -> bigrams, "3" -> trigrams.
+    ngrams: HashMap<String, Vec<String>>,
+}
+
+/// A fully resolved detection model: which languages to test for, the
+/// default confidence threshold, and the forbidden n-gram table grouped by
+/// window order.
+pub struct Model {
+    pub languages: Vec<Language>,
+    pub threshold: f64,
+    pub ngrams: HashMap<usize, HashSet<String>>,
+}
+
+/// Loads a [`Model`] from `path`, sniffing JSON vs. TOML from the extension.
+pub fn load_model(path: &str) -> Result<Model, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("json");
+
+    let raw: ModelFile = if ext.eq_ignore_ascii_case("toml") {
+        toml::from_str(&contents)?
+    } else {
+        serde_json::from_str(&contents)?
+    };
+
+    let ngrams = raw.ngrams.into_iter().map(|(order, grams)| {
+        let order: usize = order.parse().map_err(|_| format!("invalid ngram order '{}': expected an integer", order))?;
+        Ok((order, grams))
+    }).collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    Ok(Model {
+        languages: raw.languages.iter().map(|name| parse_language(name)).collect::<Result<_, _>>()?,
+        threshold: raw.threshold,
+        ngrams: group_ngrams_by_order(ngrams.into_iter()),
+    })
+}
+
+fn group_ngrams_by_order(entries: impl Iterator<Item = (usize, Vec<String>)>) -> HashMap<usize, HashSet<String>> {
+    let mut table: HashMap<usize, HashSet<String>> = HashMap::new();
+    for (order, grams) in entries {
+        table.entry(order).or_default().extend(grams);
+    }
+    table
+}
+
+/// Maps a model file's language name (e.g. "English") to a `lingua::Language`
+/// variant, so `--model` can select any of the ~75 languages `lingua`
+/// supports, not just the six the default model ships with.
+fn parse_language(name: &str) -> Result<Language, Box<dyn std::error::Error>> {
+    name.parse::<Language>().map_err(|_| format!("unknown language '{}'", name).into())
+}
+
+/// The model used when no `--model` flag is given: the six languages and
+/// bigram blocklist that this tool has always shipped with.
+pub fn default_model() -> Model {
+    let bigrams: &[&str] = &[
+        "bk", "fq", "jc", "jt", "mj", "qh", "qx", "vj", "wz", "zh",
+        "bq", "fv", "jd", "jv", "mq", "qj", "qy", "vk", "xb", "zj",
+        "bx", "fx", "jf", "jw", "mx", "qk", "qz", "vm", "xg", "zn",
+        "cb", "fz", "jg", "jx", "mz", "ql", "sx", "vn", "xj", "zq",
+        "cf", "gq", "jh", "jy", "pq", "qm", "sz", "vp", "xk", "zr",
+        "cg", "gv", "jk", "jz", "pv", "qn", "tq", "vq", "xv", "zs",
+        "cj", "gx", "jl", "kq", "px", "qo", "tx", "vt", "xz", "zx",
+        "cp", "hk", "jm", "kv", "qb", "qp", "vb", "vw", "yq",
+        "cv", "hv", "jn", "kx", "qc", "qr", "vc", "vx", "yv",
+        "cw", "hx", "jp", "kz", "qd", "qs", "vd", "vz", "yz",
+        "cx", "hz", "jq", "lq", "qe", "qt", "vf", "wq", "zb",
+        "dx", "iy", "jr", "lx", "qf", "qv", "vg", "wv", "zc",
+        "fk", "jb", "js", "mg", "qg", "qw", "vh", "wx", "zg",
+    ];
+
+    let mut ngrams = HashMap::new();
+    ngrams.insert(2, bigrams.iter().map(|s| s.to_string()).collect());
+
+    Model {
+        languages: vec![
+            Language::English, Language::French,
+            Language::German, Language::Spanish,
+            Language::Russian, Language::Chinese,
+        ],
+        threshold: 0.5,
+        ngrams,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_language_names() {
+        assert_eq!(parse_language("English").unwrap(), Language::English);
+        assert_eq!(parse_language("Chinese").unwrap(), Language::Chinese);
+    }
+
+    #[test]
+    fn parses_languages_outside_the_default_six() {
+        assert_eq!(parse_language("Italian").unwrap(), Language::Italian);
+        assert_eq!(parse_language("Japanese").unwrap(), Language::Japanese);
+    }
+
+    #[test]
+    fn rejects_unknown_language_names() {
+        assert!(parse_language("Klingon").is_err());
+    }
+}
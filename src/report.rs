@@ -0,0 +1,124 @@
+use serde::Serialize;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Output format for the final string list, mirroring `--format` on other
+/// strings-style tools: plain text by default, or a structured report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            other => Err(format!("unknown format '{}': expected text, json or csv", other)),
+        }
+    }
+}
+
+/// Numeric base used to print offsets in `--format text` mode, mirroring
+/// GNU `strings -t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Octal,
+    Decimal,
+    Hex,
+}
+
+impl FromStr for Radix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "o" => Ok(Radix::Octal),
+            "d" => Ok(Radix::Decimal),
+            "x" => Ok(Radix::Hex),
+            other => Err(format!("unknown radix '{}': expected o, d or x", other)),
+        }
+    }
+}
+
+pub fn format_offset(offset: usize, radix: Radix) -> String {
+    match radix {
+        Radix::Octal => format!("{:o}", offset),
+        Radix::Decimal => format!("{}", offset),
+        Radix::Hex => format!("{:x}", offset),
+    }
+}
+
+/// One surviving string plus everything a downstream triage tool would want
+/// to correlate it back to the input file: where it came from.
+///
+/// Every record here has, by construction, passed every filter stage
+/// (length, language, ngram, similarity) -- that's what makes it a
+/// survivor -- so there's no per-stage pass/fail to report.
+#[derive(Debug, Serialize)]
+pub struct StringRecord {
+    pub string: String,
+    pub offset_decimal: usize,
+    pub offset_hex: String,
+    pub length: usize,
+    pub language_confidence: f64,
+}
+
+impl StringRecord {
+    pub fn new(string: String, offset: usize, language_confidence: f64) -> Self {
+        StringRecord {
+            length: string.chars().count(),
+            offset_decimal: offset,
+            offset_hex: format!("{:x}", offset),
+            language_confidence,
+            string,
+        }
+    }
+}
+
+/// Writes `records` to `out` as JSON or CSV.
+pub fn write_report(records: &[StringRecord], format: Format, out: &mut dyn Write) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(out, records)?;
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for record in records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
+        Format::Text => unreachable!("text format is handled by the plain-text printer, not write_report"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trips_a_record() {
+        let records = vec![StringRecord::new("hello world".to_string(), 42, 0.91)];
+
+        let mut out = Vec::new();
+        write_report(&records, Format::Csv, &mut out).expect("csv serialization should not fail");
+
+        let csv_text = String::from_utf8(out).unwrap();
+        let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+        let row: std::collections::HashMap<String, String> = reader
+            .deserialize::<std::collections::HashMap<String, String>>()
+            .next()
+            .expect("expected one csv row")
+            .expect("row should parse");
+
+        assert_eq!(row.get("string").unwrap(), "hello world");
+        assert_eq!(row.get("offset_decimal").unwrap(), "42");
+    }
+}